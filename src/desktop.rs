@@ -1,23 +1,37 @@
 // Desktop management module for virtual desktop detection and switching
 // Cross-platform implementation for macOS and Linux
 
+use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextMapping {
     pub desktop_id: u32,
     pub room_ids: Vec<String>,
     pub notification_settings: NotificationSettings,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationSettings {
     pub enabled: bool,
     pub sound_enabled: bool,
     pub sound_file: Option<String>,
 }
 
+/// Schema version of the persisted context file, bumped whenever
+/// `PersistedContext`'s shape changes so `ContextManager::load` can migrate
+/// older files instead of discarding them.
+const CONTEXT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedContext {
+    schema_version: u32,
+    mappings: HashMap<u32, ContextMapping>,
+}
+
 pub struct ContextManager {
     mappings: HashMap<u32, ContextMapping>,
     current_desktop: u32,
@@ -31,6 +45,51 @@ impl ContextManager {
         }
     }
 
+    /// Default on-disk location for the persisted context mappings, in the
+    /// platform config directory.
+    pub fn default_path() -> Option<PathBuf> {
+        let dirs = ProjectDirs::from("dev", "matrix-ui", "matrix-ui")?;
+        Some(dirs.config_dir().join("context.ron"))
+    }
+
+    /// Loads desktop->room mappings from a RON file saved by `save`. Returns
+    /// a fresh, empty manager if the file doesn't exist yet or can't be
+    /// parsed, so a corrupt file never blocks startup.
+    pub fn load(path: &Path) -> Self {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return Self::new();
+        };
+
+        let Ok(persisted) = ron::from_str::<PersistedContext>(&contents) else {
+            return Self::new();
+        };
+
+        // No migrations exist yet; a mismatched version is treated as
+        // incompatible and discarded rather than guessed at.
+        if persisted.schema_version != CONTEXT_SCHEMA_VERSION {
+            return Self::new();
+        }
+
+        Self {
+            mappings: persisted.mappings,
+            current_desktop: 0,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let persisted = PersistedContext {
+            schema_version: CONTEXT_SCHEMA_VERSION,
+            mappings: self.mappings.clone(),
+        };
+        let contents = ron::ser::to_string_pretty(&persisted, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
     pub fn get_current_desktop(&self) -> u32 {
         self.current_desktop
     }
@@ -52,11 +111,20 @@ impl ContextManager {
                 sound_file: None,
             },
         });
-        
+
         if !mapping.room_ids.contains(&room_id) {
             mapping.room_ids.push(room_id);
         }
     }
+
+    pub fn set_notification_settings(&mut self, desktop_id: u32, settings: NotificationSettings) {
+        let mapping = self.mappings.entry(desktop_id).or_insert_with(|| ContextMapping {
+            desktop_id,
+            room_ids: Vec::new(),
+            notification_settings: settings.clone(),
+        });
+        mapping.notification_settings = settings;
+    }
 }
 
 // Cross-platform desktop management trait
@@ -65,6 +133,12 @@ pub trait DesktopManager {
     fn get_desktop_count(&self) -> Result<u32, Box<dyn std::error::Error>>;
     fn switch_to_desktop(&self, desktop: u32) -> Result<(), Box<dyn std::error::Error>>;
     fn get_desktop_name(&self, desktop: u32) -> Result<String, Box<dyn std::error::Error>>;
+
+    /// Starts watching for desktop switches and returns a receiver that
+    /// yields the new desktop index each time one occurs, so callers can
+    /// drive `ContextManager` + `ChatCarousel::filter_by_desktop` reactively
+    /// instead of polling `get_current_desktop`.
+    fn watch_desktop_changes(&self) -> Receiver<u32>;
 }
 
 // Platform-specific implementations will be added here
@@ -93,43 +167,362 @@ impl DesktopManager for MacOSDesktopManager {
         // TODO: Implement desktop name retrieval
         Ok(format!("Desktop {}", desktop))
     }
+
+    fn watch_desktop_changes(&self) -> Receiver<u32> {
+        // TODO: Subscribe to NSWorkspace space-change notifications instead
+        // of returning an immediately-closed channel.
+        mpsc::channel().1
+    }
 }
 
 #[cfg(target_os = "linux")]
-pub struct LinuxDesktopManager;
+mod x11 {
+    //! EWMH-based desktop queries and change notifications, implemented
+    //! directly against Xlib via `x11-dl` (no higher-level window-manager
+    //! crate, to keep the dependency surface small).
+
+    use std::ffi::CString;
+    use std::os::raw::{c_long, c_uchar};
+    use std::ptr;
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread;
+
+    use x11_dl::xlib::{self, Xlib};
+
+    pub struct X11Connection {
+        xlib: Xlib,
+        display: *mut xlib::Display,
+        root: xlib::Window,
+        net_current_desktop: xlib::Atom,
+        net_number_of_desktops: xlib::Atom,
+        net_desktop_names: xlib::Atom,
+        utf8_string: xlib::Atom,
+    }
+
+    // `watch_desktop_changes` spawns a thread that blocks in `XNextEvent`
+    // on this same `Display*` while the owning thread (or a tray callback
+    // dispatched on yet another thread) can still call `current_desktop`,
+    // `switch_to_desktop`, etc. Xlib only supports that once `XInitThreads`
+    // has been called before the display is opened, which `open` does below.
+    unsafe impl Send for X11Connection {}
+    unsafe impl Sync for X11Connection {}
+
+    impl X11Connection {
+        pub fn open() -> Result<Self, Box<dyn std::error::Error>> {
+            let xlib = Xlib::open()?;
+
+            unsafe {
+                // Must happen before `XOpenDisplay`: it puts Xlib into a mode
+                // that locks the display around each call, which is required
+                // as soon as more than one thread touches it concurrently.
+                (xlib.XInitThreads)();
+
+                let display = (xlib.XOpenDisplay)(ptr::null());
+                if display.is_null() {
+                    return Err("unable to open X11 display".into());
+                }
+                let root = (xlib.XDefaultRootWindow)(display);
+
+                let net_current_desktop = Self::intern(&xlib, display, "_NET_CURRENT_DESKTOP");
+                let net_number_of_desktops = Self::intern(&xlib, display, "_NET_NUMBER_OF_DESKTOPS");
+                let net_desktop_names = Self::intern(&xlib, display, "_NET_DESKTOP_NAMES");
+                let utf8_string = Self::intern(&xlib, display, "UTF8_STRING");
+
+                Ok(Self {
+                    xlib,
+                    display,
+                    root,
+                    net_current_desktop,
+                    net_number_of_desktops,
+                    net_desktop_names,
+                    utf8_string,
+                })
+            }
+        }
+
+        unsafe fn intern(xlib: &Xlib, display: *mut xlib::Display, name: &str) -> xlib::Atom {
+            let cname = CString::new(name).expect("atom name has no interior NUL");
+            (xlib.XInternAtom)(display, cname.as_ptr(), xlib::False)
+        }
+
+        fn read_cardinal(&self, property: xlib::Atom) -> Option<u32> {
+            unsafe {
+                let mut actual_type: xlib::Atom = 0;
+                let mut actual_format: i32 = 0;
+                let mut nitems: u64 = 0;
+                let mut bytes_after: u64 = 0;
+                let mut data: *mut c_uchar = ptr::null_mut();
+
+                let status = (self.xlib.XGetWindowProperty)(
+                    self.display,
+                    self.root,
+                    property,
+                    0,
+                    1,
+                    xlib::False,
+                    xlib::XA_CARDINAL,
+                    &mut actual_type,
+                    &mut actual_format,
+                    &mut nitems,
+                    &mut bytes_after,
+                    &mut data,
+                );
+
+                if status != xlib::Success as i32 || data.is_null() || nitems == 0 {
+                    return None;
+                }
+
+                // A non-compliant window manager could report this property
+                // with a narrower format (8 or 16 bits); `data` would then
+                // only have 1-2 bytes allocated; reading a u32 out of it
+                // would read past the end of the buffer.
+                if actual_format != 32 || actual_type != xlib::XA_CARDINAL {
+                    (self.xlib.XFree)(data as *mut _);
+                    return None;
+                }
+
+                let value = *(data as *const u32);
+                (self.xlib.XFree)(data as *mut _);
+                Some(value)
+            }
+        }
+
+        pub fn current_desktop(&self) -> Result<u32, Box<dyn std::error::Error>> {
+            self.read_cardinal(self.net_current_desktop)
+                .ok_or_else(|| "_NET_CURRENT_DESKTOP not set by window manager".into())
+        }
+
+        pub fn desktop_count(&self) -> Result<u32, Box<dyn std::error::Error>> {
+            self.read_cardinal(self.net_number_of_desktops)
+                .ok_or_else(|| "_NET_NUMBER_OF_DESKTOPS not set by window manager".into())
+        }
+
+        pub fn desktop_name(&self, desktop: u32) -> Result<String, Box<dyn std::error::Error>> {
+            unsafe {
+                let mut actual_type: xlib::Atom = 0;
+                let mut actual_format: i32 = 0;
+                let mut nitems: u64 = 0;
+                let mut bytes_after: u64 = 0;
+                let mut data: *mut c_uchar = ptr::null_mut();
+
+                let status = (self.xlib.XGetWindowProperty)(
+                    self.display,
+                    self.root,
+                    self.net_desktop_names,
+                    0,
+                    1024,
+                    xlib::False,
+                    self.utf8_string,
+                    &mut actual_type,
+                    &mut actual_format,
+                    &mut nitems,
+                    &mut bytes_after,
+                    &mut data,
+                );
+
+                if status != xlib::Success as i32 || data.is_null() {
+                    return Err("_NET_DESKTOP_NAMES not set by window manager".into());
+                }
+
+                let bytes = std::slice::from_raw_parts(data, nitems as usize);
+                let names: Vec<String> = bytes
+                    .split(|&b| b == 0)
+                    .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+                    .filter(|name| !name.is_empty())
+                    .collect();
+
+                (self.xlib.XFree)(data as *mut _);
+
+                names
+                    .into_iter()
+                    .nth(desktop as usize)
+                    .ok_or_else(|| format!("no desktop name at index {desktop}").into())
+            }
+        }
+
+        pub fn switch_to_desktop(&self, desktop: u32) -> Result<(), Box<dyn std::error::Error>> {
+            unsafe {
+                let mut event: xlib::XClientMessageEvent = std::mem::zeroed();
+                event.type_ = xlib::ClientMessage;
+                event.window = self.root;
+                event.message_type = self.net_current_desktop;
+                event.format = 32;
+                event.data.set_long(0, desktop as c_long);
+                event.data.set_long(1, xlib::CurrentTime as c_long);
+
+                let mut xevent = xlib::XEvent { client_message: event };
+
+                let mask = xlib::SubstructureNotifyMask | xlib::SubstructureRedirectMask;
+                let status = (self.xlib.XSendEvent)(self.display, self.root, xlib::False, mask, &mut xevent);
+                (self.xlib.XFlush)(self.display);
+
+                if status == 0 {
+                    return Err("XSendEvent failed".into());
+                }
+                Ok(())
+            }
+        }
+
+        /// Selects `PropertyChangeMask` on the root window and spawns a
+        /// thread that blocks on `XNextEvent`, forwarding the new desktop
+        /// index over the returned channel whenever `_NET_CURRENT_DESKTOP`
+        /// changes.
+        pub fn watch_desktop_changes(self: std::sync::Arc<Self>) -> Receiver<u32> {
+            let (tx, rx) = mpsc::channel();
+
+            unsafe {
+                (self.xlib.XSelectInput)(self.display, self.root, xlib::PropertyChangeMask);
+            }
+
+            thread::spawn(move || loop {
+                let mut event: xlib::XEvent = unsafe { std::mem::zeroed() };
+                unsafe {
+                    (self.xlib.XNextEvent)(self.display, &mut event);
+                }
+
+                if event.get_type() != xlib::PropertyNotify {
+                    continue;
+                }
+
+                let changed = unsafe { event.property.atom };
+                if changed != self.net_current_desktop {
+                    continue;
+                }
+
+                match self.current_desktop() {
+                    Ok(desktop) => {
+                        if tx.send(desktop).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => continue,
+                }
+            });
+
+            rx
+        }
+    }
+
+    impl Drop for X11Connection {
+        fn drop(&mut self) {
+            unsafe {
+                (self.xlib.XCloseDisplay)(self.display);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub struct LinuxDesktopManager {
+    connection: std::sync::Arc<x11::X11Connection>,
+}
+
+#[cfg(target_os = "linux")]
+impl LinuxDesktopManager {
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            connection: std::sync::Arc::new(x11::X11Connection::open()?),
+        })
+    }
+}
 
 #[cfg(target_os = "linux")]
 impl DesktopManager for LinuxDesktopManager {
     fn get_current_desktop(&self) -> Result<u32, Box<dyn std::error::Error>> {
-        // TODO: Implement using EWMH _NET_CURRENT_DESKTOP
-        Ok(0)
+        self.connection.current_desktop()
     }
 
     fn get_desktop_count(&self) -> Result<u32, Box<dyn std::error::Error>> {
-        // TODO: Implement using EWMH _NET_NUMBER_OF_DESKTOPS
-        Ok(1)
+        self.connection.desktop_count()
     }
 
     fn switch_to_desktop(&self, desktop: u32) -> Result<(), Box<dyn std::error::Error>> {
-        // TODO: Use EWMH _NET_CURRENT_DESKTOP property
-        println!("Switching to desktop {} on Linux", desktop);
-        Ok(())
+        self.connection.switch_to_desktop(desktop)
     }
 
     fn get_desktop_name(&self, desktop: u32) -> Result<String, Box<dyn std::error::Error>> {
-        // TODO: Implement desktop name retrieval from EWMH
-        Ok(format!("Desktop {}", desktop))
+        self.connection.desktop_name(desktop)
+    }
+
+    fn watch_desktop_changes(&self) -> Receiver<u32> {
+        self.connection.clone().watch_desktop_changes()
     }
 }
 
-// Factory function to create platform-specific desktop manager
-pub fn create_desktop_manager() -> Box<dyn DesktopManager> {
+/// Factory function to create the platform-specific desktop manager.
+///
+/// Fails rather than panicking when the platform backend can't connect
+/// (e.g. Linux without an X11 display available, such as a pure-Wayland
+/// session, a container, or SSH without X forwarding), so callers can
+/// disable desktop tracking instead of taking down the thread that calls it.
+pub fn create_desktop_manager() -> Result<Box<dyn DesktopManager>, Box<dyn std::error::Error>> {
     #[cfg(target_os = "macos")]
-    return Box::new(MacOSDesktopManager);
-    
+    return Ok(Box::new(MacOSDesktopManager));
+
     #[cfg(target_os = "linux")]
-    return Box::new(LinuxDesktopManager);
-    
+    return Ok(Box::new(LinuxDesktopManager::new()?));
+
     #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     panic!("Unsupported platform");
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("matrix-ui-test-{}-{}.ron", std::process::id(), name))
+    }
+
+    #[test]
+    fn round_trips_mappings_through_ron() {
+        let path = temp_path("round-trip");
+
+        let mut manager = ContextManager::new();
+        manager.add_room_to_desktop(0, "!general:example.org".into());
+        manager.add_room_to_desktop(0, "!random:example.org".into());
+        manager.add_room_to_desktop(1, "!work:example.org".into());
+        manager.set_notification_settings(
+            1,
+            NotificationSettings {
+                enabled: false,
+                sound_enabled: false,
+                sound_file: Some("silence.ogg".into()),
+            },
+        );
+
+        manager.save(&path).expect("save should succeed");
+        let loaded = ContextManager::load(&path);
+
+        assert_eq!(
+            loaded.get_rooms_for_desktop(0),
+            vec!["!general:example.org".to_string(), "!random:example.org".to_string()]
+        );
+        assert_eq!(loaded.get_rooms_for_desktop(1), vec!["!work:example.org".to_string()]);
+        assert_eq!(loaded.mappings[&1].notification_settings.sound_file.as_deref(), Some("silence.ogg"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn load_returns_empty_manager_for_missing_file() {
+        let path = temp_path("missing");
+        let manager = ContextManager::load(&path);
+        assert_eq!(manager.get_rooms_for_desktop(0), Vec::<String>::new());
+    }
+
+    #[test]
+    fn load_discards_mismatched_schema_version() {
+        let path = temp_path("bad-version");
+        let stale = PersistedContext {
+            schema_version: CONTEXT_SCHEMA_VERSION + 1,
+            mappings: HashMap::new(),
+        };
+        std::fs::write(&path, ron::ser::to_string(&stale).unwrap()).unwrap();
+
+        let manager = ContextManager::load(&path);
+        assert_eq!(manager.get_current_desktop(), 0);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,291 @@
+// Global hotkey subsystem: parses human-readable chords from a user config
+// file, registers them as OS-level global shortcuts, and dispatches to the
+// chat switcher. Reloadable at runtime so editing the config file doesn't
+// require a restart.
+
+use directories::ProjectDirs;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+
+use crate::AppState;
+
+/// Actions the switcher can be bound to. The config file maps one chord to
+/// each action; unmentioned actions fall back to `default_chord`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    LocalSearch,
+    GlobalSearch,
+}
+
+impl Action {
+    fn config_key(self) -> &'static str {
+        match self {
+            Action::LocalSearch => "local_search",
+            Action::GlobalSearch => "global_search",
+        }
+    }
+
+    fn default_chord(self) -> &'static str {
+        match self {
+            Action::LocalSearch => "Ctrl+Shift+K",
+            Action::GlobalSearch => "Ctrl+Alt+Space",
+        }
+    }
+
+    fn all() -> [Action; 2] {
+        [Action::LocalSearch, Action::GlobalSearch]
+    }
+
+    fn global(self) -> bool {
+        matches!(self, Action::GlobalSearch)
+    }
+}
+
+/// A parsed, normalized chord: modifier flags plus a key code, independent
+/// of how it was spelled in the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chord {
+    modifiers: Modifiers,
+    code: Code,
+}
+
+impl Chord {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = text.split('+').map(str::trim).filter(|s| !s.is_empty()).collect();
+        let Some((key, mods)) = parts.split_last() else {
+            return Err(format!("empty chord '{text}'"));
+        };
+
+        let mut modifiers = Modifiers::empty();
+        for modifier in mods {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => Modifiers::CONTROL,
+                "shift" => Modifiers::SHIFT,
+                "alt" | "option" => Modifiers::ALT,
+                "super" | "cmd" | "command" | "meta" | "win" => Modifiers::META,
+                other => return Err(format!("unknown modifier '{other}' in chord '{text}'")),
+            };
+        }
+
+        let code = parse_key_code(key).ok_or_else(|| format!("unknown key '{key}' in chord '{text}'"))?;
+        Ok(Chord { modifiers, code })
+    }
+
+    fn to_shortcut(self) -> Shortcut {
+        Shortcut::new(Some(self.modifiers), self.code)
+    }
+}
+
+fn parse_key_code(key: &str) -> Option<Code> {
+    let upper = key.to_uppercase();
+    if upper.len() == 1 {
+        let c = upper.chars().next()?;
+        if c.is_ascii_alphabetic() {
+            return Some(match c {
+                'A' => Code::KeyA,
+                'B' => Code::KeyB,
+                'C' => Code::KeyC,
+                'D' => Code::KeyD,
+                'E' => Code::KeyE,
+                'F' => Code::KeyF,
+                'G' => Code::KeyG,
+                'H' => Code::KeyH,
+                'I' => Code::KeyI,
+                'J' => Code::KeyJ,
+                'K' => Code::KeyK,
+                'L' => Code::KeyL,
+                'M' => Code::KeyM,
+                'N' => Code::KeyN,
+                'O' => Code::KeyO,
+                'P' => Code::KeyP,
+                'Q' => Code::KeyQ,
+                'R' => Code::KeyR,
+                'S' => Code::KeyS,
+                'T' => Code::KeyT,
+                'U' => Code::KeyU,
+                'V' => Code::KeyV,
+                'W' => Code::KeyW,
+                'X' => Code::KeyX,
+                'Y' => Code::KeyY,
+                'Z' => Code::KeyZ,
+                _ => return None,
+            });
+        }
+        if c.is_ascii_digit() {
+            return Some(match c {
+                '0' => Code::Digit0,
+                '1' => Code::Digit1,
+                '2' => Code::Digit2,
+                '3' => Code::Digit3,
+                '4' => Code::Digit4,
+                '5' => Code::Digit5,
+                '6' => Code::Digit6,
+                '7' => Code::Digit7,
+                '8' => Code::Digit8,
+                '9' => Code::Digit9,
+                _ => return None,
+            });
+        }
+    }
+
+    match upper.as_str() {
+        "SPACE" => Some(Code::Space),
+        "ENTER" | "RETURN" => Some(Code::Enter),
+        "ESC" | "ESCAPE" => Some(Code::Escape),
+        "TAB" => Some(Code::Tab),
+        _ => None,
+    }
+}
+
+/// One parsed binding plus the source line it came from, so conflicts can
+/// be reported back to the user in terms of the file they edited.
+struct ParsedBinding {
+    action: Action,
+    chord: Chord,
+    line: usize,
+}
+
+/// Parses the config file's "action = chord" lines, filling in defaults for
+/// any action the file doesn't mention, and rejects duplicate/conflicting
+/// chords by reporting the two line numbers involved.
+fn parse_config(contents: &str) -> Result<Vec<ParsedBinding>, String> {
+    let mut bindings = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for (index, raw_line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {line_number}: expected 'action = chord', got '{line}'"));
+        };
+        let key = key.trim();
+        let value = value.trim();
+
+        let action = Action::all()
+            .into_iter()
+            .find(|action| action.config_key() == key)
+            .ok_or_else(|| format!("line {line_number}: unknown action '{key}'"))?;
+
+        if let Some(previous) = seen.insert(key.to_string(), line_number) {
+            return Err(format!("line {line_number}: action '{key}' already bound on line {previous}"));
+        }
+
+        let chord = Chord::parse(value).map_err(|e| format!("line {line_number}: {e}"))?;
+        bindings.push(ParsedBinding { action, chord, line: line_number });
+    }
+
+    for action in Action::all() {
+        if !bindings.iter().any(|b| b.action == action) {
+            let chord = Chord::parse(action.default_chord()).expect("default chords are always valid");
+            bindings.push(ParsedBinding { action, chord, line: 0 });
+        }
+    }
+
+    // Validate the full merged set (explicit bindings plus back-filled
+    // defaults) so an explicit binding that happens to collide with another
+    // action's *default* chord is caught here too, not just collisions
+    // between two explicit lines.
+    let mut by_chord: HashMap<Chord, (usize, Action)> = HashMap::new();
+    for binding in &bindings {
+        if let Some(&(other_line, other_action)) = by_chord.get(&binding.chord) {
+            // Report the error against whichever side of the collision is an
+            // explicit line in the file; a back-filled default (line 0) has
+            // nothing to point at, so name the action it belongs to instead.
+            let message = match (binding.line, other_line) {
+                (0, 0) => unreachable!("default chords never collide with each other"),
+                (0, other_line) => format!(
+                    "line {other_line}: chord conflicts with the default binding for '{}'",
+                    binding.action.config_key()
+                ),
+                (line, 0) => format!(
+                    "line {line}: chord conflicts with the default binding for '{}'",
+                    other_action.config_key()
+                ),
+                (line, other_line) => format!("line {line}: chord conflicts with binding on line {other_line}"),
+            };
+            return Err(message);
+        }
+        by_chord.insert(binding.chord, (binding.line, binding.action));
+    }
+
+    Ok(bindings)
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    let dirs = ProjectDirs::from("dev", "matrix-ui", "matrix-ui")?;
+    Some(dirs.config_dir().join("keybindings.txt"))
+}
+
+fn default_config_contents() -> String {
+    format!(
+        "# One binding per line: action = chord\n{} = {}\n{} = {}\n",
+        Action::LocalSearch.config_key(),
+        Action::LocalSearch.default_chord(),
+        Action::GlobalSearch.config_key(),
+        Action::GlobalSearch.default_chord(),
+    )
+}
+
+fn read_or_create_config(path: &std::path::Path) -> Result<String, String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(contents),
+        Err(_) => {
+            let contents = default_config_contents();
+            if let Some(parent) = path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            let _ = std::fs::write(path, &contents);
+            Ok(contents)
+        }
+    }
+}
+
+/// (Re)reads the keybinding config file, validates it, unregisters whatever
+/// shortcuts were previously active, and registers the new ones. Returns a
+/// descriptive error (including the offending line) on a bad config instead
+/// of partially applying it.
+pub fn load_and_register(app: &AppHandle) -> Result<(), String> {
+    let path = config_path().ok_or("could not determine config directory")?;
+    let contents = read_or_create_config(&path)?;
+    let bindings = parse_config(&contents)?;
+
+    let shortcuts = app.global_shortcut();
+    let _ = shortcuts.unregister_all();
+
+    for binding in &bindings {
+        let action = binding.action;
+        let global = action.global();
+        shortcuts
+            .on_shortcut(binding.chord.to_shortcut(), move |app, _shortcut, event| {
+                if event.state() != ShortcutState::Pressed {
+                    return;
+                }
+                dispatch(app, action, global);
+            })
+            .map_err(|e| format!("failed to register chord for '{}': {e}", action.config_key()))?;
+    }
+
+    Ok(())
+}
+
+fn dispatch(app: &AppHandle, _action: Action, global: bool) {
+    if let Some(state) = app.try_state::<AppState>() {
+        tauri::async_runtime::spawn({
+            let ui = state.ui.clone();
+            async move {
+                ui.lock().await.switcher.show(global);
+            }
+        });
+    }
+
+    if let Some(window) = app.get_webview_window(crate::switcher::SWITCHER_LABEL) {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
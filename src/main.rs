@@ -1,11 +1,38 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-// mod matrix;  // Commented out for initial scaffolding
 mod desktop;
+mod hotkeys;
+mod matrix;
+mod settings;
+mod switcher;
+mod tray;
 mod ui;
 
-use tauri::Manager;
+use matrix::{MatrixClient, MatrixConfig, MatrixEvent};
+use settings::AppSettings;
+use std::sync::Arc;
+use tauri::{tray::TrayIcon, Manager};
+use tokio::sync::Mutex;
+use ui::UIState;
+
+pub(crate) struct AppState {
+    pub(crate) matrix: Arc<Mutex<MatrixClient>>,
+    pub(crate) ui: Arc<Mutex<UIState>>,
+    pub(crate) tray: Mutex<Option<TrayIcon>>,
+    pub(crate) settings: Mutex<AppSettings>,
+    pub(crate) context: Arc<Mutex<desktop::ContextManager>>,
+}
+
+fn to_ui_room(room: matrix::Room) -> ui::Room {
+    ui::Room {
+        id: room.id,
+        name: room.name,
+        avatar_url: room.avatar_url,
+        last_message: room.last_message,
+        unread_count: room.unread_count,
+    }
+}
 
 // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
 #[tauri::command]
@@ -13,15 +40,169 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+#[tauri::command]
+async fn matrix_login(
+    state: tauri::State<'_, AppState>,
+    homeserver: String,
+    username: String,
+    password: String,
+    device_name: String,
+) -> Result<(), String> {
+    let config = MatrixConfig {
+        homeserver,
+        username,
+        device_name,
+    };
+
+    let mut client = state.matrix.lock().await;
+    client.login(config, &password).await.map_err(|e| e.to_string())?;
+    client.start_sync().await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn matrix_rooms(state: tauri::State<'_, AppState>) -> Result<Vec<ui::Room>, String> {
+    let client = state.matrix.lock().await;
+    let rooms = client.get_rooms().await;
+    Ok(rooms.into_values().map(to_ui_room).collect())
+}
+
+#[tauri::command]
+async fn set_switcher_always_visible(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    always_visible: bool,
+) -> Result<(), String> {
+    let mut settings = state.settings.lock().await;
+    settings.switcher_always_visible = always_visible;
+    settings.save().map_err(|e| e.to_string())?;
+
+    switcher::set_always_visible(&app, always_visible).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn add_room_to_desktop(state: tauri::State<'_, AppState>, desktop_id: u32, room_id: String) -> Result<(), String> {
+    let mut context = state.context.lock().await;
+    context.add_room_to_desktop(desktop_id, room_id);
+    context.save(&context_path()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_desktop_notification_settings(
+    state: tauri::State<'_, AppState>,
+    desktop_id: u32,
+    settings: desktop::NotificationSettings,
+) -> Result<(), String> {
+    let mut context = state.context.lock().await;
+    context.set_notification_settings(desktop_id, settings);
+    context.save(&context_path()).map_err(|e| e.to_string())
+}
+
+fn context_path() -> std::path::PathBuf {
+    desktop::ContextManager::default_path().unwrap_or_else(|| std::path::PathBuf::from("context.ron"))
+}
+
+#[tauri::command]
+fn reload_keybindings(app: tauri::AppHandle) -> Result<(), String> {
+    hotkeys::load_and_register(&app)
+}
+
+/// Drains Matrix sync events and applies them to `UIState`, so newly
+/// arriving traffic bumps the relevant avatar to the front of the carousel.
+/// Also keeps the tray icon and quick-switch menu in sync with unread state.
+async fn run_event_consumer(mut events: matrix::MatrixEventReceiver, app: tauri::AppHandle) {
+    let state = app.state::<AppState>();
+
+    while let Some(event) = events.recv().await {
+        let mut ui_state = state.ui.lock().await;
+        match event {
+            MatrixEvent::RoomUpdated(room) => {
+                ui_state.carousel.upsert_avatar(&to_ui_room(room));
+                drop(ui_state);
+
+                if let Some(tray_icon) = state.tray.lock().await.as_ref() {
+                    let rooms: Vec<ui::Room> = state.matrix.lock().await.get_rooms().await.into_values().map(to_ui_room).collect();
+                    let _ = tray::rebuild_room_menu(&app, tray_icon, &rooms);
+                }
+            }
+            MatrixEvent::NewMessage { room_id, .. } => {
+                ui_state.carousel.animate_new_message(&room_id);
+            }
+            MatrixEvent::UnreadChanged => {
+                if let Some(tray_icon) = state.tray.lock().await.as_ref() {
+                    let has_unread = tray::carousel_has_unread(&ui_state.carousel);
+                    let _ = tray::set_unread(tray_icon, has_unread);
+                }
+            }
+        }
+    }
+}
+
+/// Watches for virtual-desktop switches and applies `ContextManager`'s
+/// room mapping for the new desktop to the carousel, so the visible avatars
+/// follow the user across spaces without any polling.
+fn spawn_desktop_watcher(ui: Arc<Mutex<UIState>>, context: Arc<Mutex<desktop::ContextManager>>) {
+    std::thread::spawn(move || {
+        let manager = match desktop::create_desktop_manager() {
+            Ok(manager) => manager,
+            Err(err) => {
+                eprintln!("desktop tracking disabled: {err}");
+                return;
+            }
+        };
+        let changes = manager.watch_desktop_changes();
+
+        for desktop_id in changes {
+            let room_ids = context.blocking_lock().get_rooms_for_desktop(desktop_id);
+            ui.blocking_lock().carousel.filter_by_desktop(desktop_id, &room_ids);
+        }
+    });
+}
+
 fn main() {
+    let (matrix_client, event_rx) = MatrixClient::new();
+    let ui_state = Arc::new(Mutex::new(UIState::new()));
+    let settings = AppSettings::load();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![greet])
-        .setup(|app| {
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .manage(AppState {
+            matrix: Arc::new(Mutex::new(matrix_client)),
+            ui: ui_state,
+            tray: Mutex::new(None),
+            settings: Mutex::new(settings),
+            context: Arc::new(Mutex::new(desktop::ContextManager::load(&context_path()))),
+        })
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            matrix_login,
+            matrix_rooms,
+            set_switcher_always_visible,
+            add_room_to_desktop,
+            set_desktop_notification_settings,
+            reload_keybindings
+        ])
+        .setup(move |app| {
             // Initialize the app
             println!("Matrix UI starting...");
+
+            let tray_icon = tray::init_tray(app.handle())?;
+            let state = app.state::<AppState>();
+            *state.tray.blocking_lock() = Some(tray_icon);
+
+            let always_visible = state.settings.blocking_lock().switcher_always_visible;
+            switcher::create_switcher_window(app.handle(), always_visible)?;
+
+            spawn_desktop_watcher(state.ui.clone(), state.context.clone());
+
+            if let Err(err) = hotkeys::load_and_register(app.handle()) {
+                eprintln!("failed to register global hotkeys: {err}");
+            }
+
+            tauri::async_runtime::spawn(run_event_consumer(event_rx, app.handle().clone()));
             Ok(())
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
-}
\ No newline at end of file
+}
@@ -1,10 +1,16 @@
 // Matrix protocol integration module
-// This will handle Matrix SDK integration for chat functionality
+// This handles Matrix SDK integration for chat functionality
 
-// Temporarily commented out for initial scaffolding
-// use matrix_sdk::{Client, config::SyncSettings};
+use matrix_sdk::{
+    config::SyncSettings,
+    room::Room as SdkRoom,
+    ruma::events::room::message::SyncRoomMessageEvent,
+    Client,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Room {
@@ -22,54 +28,144 @@ pub struct MatrixConfig {
     pub device_name: String,
 }
 
+/// Events emitted by the sync background task, consumed by the UI loop to
+/// drive `UIState` without the event handler needing a UI reference.
+#[derive(Debug, Clone)]
+pub enum MatrixEvent {
+    RoomUpdated(Room),
+    NewMessage { room_id: String, body: String },
+    UnreadChanged,
+}
+
+pub type MatrixEventSender = mpsc::UnboundedSender<MatrixEvent>;
+pub type MatrixEventReceiver = mpsc::UnboundedReceiver<MatrixEvent>;
+
 pub struct MatrixClient {
-    // client: Option<Client>,
-    rooms: HashMap<String, Room>,
+    client: Option<Client>,
+    rooms: Arc<Mutex<HashMap<String, Room>>>,
+    events: MatrixEventSender,
 }
 
 impl MatrixClient {
-    pub fn new() -> Self {
-        Self {
-            // client: None,
-            rooms: HashMap::new(),
-        }
+    /// Creates a client and the channel its sync task will use to report
+    /// events. The receiver half should be handed to the UI consumer loop.
+    pub fn new() -> (Self, MatrixEventReceiver) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (
+            Self {
+                client: None,
+                rooms: Arc::new(Mutex::new(HashMap::new())),
+                events: tx,
+            },
+            rx,
+        )
     }
 
-    pub async fn login(&mut self, _config: MatrixConfig, _password: &str) -> Result<(), Box<dyn std::error::Error>> {
-        // TODO: Implement Matrix SDK login
-        // let client = Client::new(config.homeserver.parse()?).await?;
-        // 
-        // client
-        //     .matrix_auth()
-        //     .login_username(&config.username, password)
-        //     .initial_device_display_name(&config.device_name)
-        //     .await?;
-        //
-        // self.client = Some(client);
+    pub async fn login(&mut self, config: MatrixConfig, password: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let client = Client::builder()
+            .homeserver_url(&config.homeserver)
+            .build()
+            .await?;
+
+        client
+            .matrix_auth()
+            .login_username(&config.username, password)
+            .initial_device_display_name(&config.device_name)
+            .await?;
+
+        self.client = Some(client);
         Ok(())
     }
 
+    /// Registers the room-message handler and spawns a background task that
+    /// keeps the client syncing. Incoming events are translated into
+    /// `MatrixEvent`s and pushed down the channel for the UI side to apply.
     pub async fn start_sync(&self) -> Result<(), Box<dyn std::error::Error>> {
-        // TODO: Implement Matrix sync
-        // if let Some(client) = &self.client {
-        //     // Register event handlers
-        //     // client.register_event_handler(on_room_message).await;
-        //     
-        //     // Start syncing
-        //     let sync_settings = SyncSettings::default();
-        //     client.sync_once(sync_settings).await?;
-        // }
+        let client = self
+            .client
+            .as_ref()
+            .ok_or("start_sync called before login")?
+            .clone();
+
+        let rooms = self.rooms.clone();
+        let events = self.events.clone();
+
+        client.add_event_handler(move |event: SyncRoomMessageEvent, room: SdkRoom| {
+            let rooms = rooms.clone();
+            let events = events.clone();
+            async move {
+                let body = match event.as_original() {
+                    Some(original) => original.content.body().to_string(),
+                    None => return,
+                };
+                let room_id = room.room_id().to_string();
+
+                let mut rooms = rooms.lock().await;
+                let entry = rooms.entry(room_id.clone()).or_insert_with(|| Room {
+                    id: room_id.clone(),
+                    name: room.name().unwrap_or_else(|| room_id.clone()),
+                    avatar_url: None,
+                    last_message: None,
+                    unread_count: 0,
+                });
+                entry.last_message = Some(body.clone());
+                entry.unread_count += 1;
+
+                let _ = events.send(MatrixEvent::RoomUpdated(entry.clone()));
+                let _ = events.send(MatrixEvent::NewMessage {
+                    room_id: room_id.clone(),
+                    body,
+                });
+                let _ = events.send(MatrixEvent::UnreadChanged);
+            }
+        });
+
+        // Run one sync up front so rooms the account already belongs to are
+        // reported immediately, instead of staying invisible to the UI until
+        // the first new message happens to arrive in each of them.
+        client.sync_once(SyncSettings::default()).await?;
+        self.emit_known_rooms(&client).await;
+
+        tokio::spawn(async move {
+            if let Err(err) = client.sync(SyncSettings::default()).await {
+                eprintln!("matrix sync stopped: {err}");
+            }
+        });
+
         Ok(())
     }
 
-    pub fn get_rooms(&self) -> &HashMap<String, Room> {
-        &self.rooms
+    /// Upserts every room the client currently knows about into `rooms` and
+    /// reports each as a `RoomUpdated` event, so a fresh login populates the
+    /// carousel and tray menu without waiting on message traffic.
+    async fn emit_known_rooms(&self, client: &Client) {
+        let mut rooms = self.rooms.lock().await;
+        for sdk_room in client.rooms() {
+            let room_id = sdk_room.room_id().to_string();
+            let entry = rooms.entry(room_id.clone()).or_insert_with(|| Room {
+                id: room_id.clone(),
+                name: sdk_room.name().unwrap_or_else(|| room_id.clone()),
+                avatar_url: None,
+                last_message: None,
+                unread_count: 0,
+            });
+            let _ = self.events.send(MatrixEvent::RoomUpdated(entry.clone()));
+        }
+    }
+
+    pub async fn get_rooms(&self) -> HashMap<String, Room> {
+        self.rooms.lock().await.clone()
     }
 }
 
-// Event handler for incoming messages
-// async fn on_room_message(event: SyncRoomMessageEvent, room: Room) {
-//     // Handle incoming messages for UI updates
-//     // Trigger avatar carousel animations
-//     // Apply contextual filtering based on current desktop
-// }
\ No newline at end of file
+impl Clone for Room {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            avatar_url: self.avatar_url.clone(),
+            last_message: self.last_message.clone(),
+            unread_count: self.unread_count,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// Small persisted user preferences that aren't tied to a specific module
+// (desktop context mappings have their own store in `desktop::ContextManager`).
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppSettings {
+    pub switcher_always_visible: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            switcher_always_visible: true,
+        }
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    let dirs = ProjectDirs::from("dev", "matrix-ui", "matrix-ui")?;
+    Some(dirs.config_dir().join("settings.json"))
+}
+
+impl AppSettings {
+    pub fn load() -> Self {
+        let Some(path) = settings_path() else {
+            return Self::default();
+        };
+
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = settings_path() else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents)
+    }
+}
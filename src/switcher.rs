@@ -0,0 +1,35 @@
+// Window management for the ephemeral `ChatSwitcher` overlay.
+//
+// `ChatSwitcher::show` toggles UI state, but the overlay window itself also
+// needs to stay visible when the user changes virtual desktops so it can be
+// summoned from anywhere.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+pub const SWITCHER_LABEL: &str = "switcher";
+
+/// Creates the switcher window if it doesn't already exist, applying the
+/// given "always visible on all workspaces" preference.
+pub fn create_switcher_window(app: &AppHandle, always_visible: bool) -> tauri::Result<WebviewWindow> {
+    if let Some(window) = app.get_webview_window(SWITCHER_LABEL) {
+        return Ok(window);
+    }
+
+    let window = WebviewWindowBuilder::new(app, SWITCHER_LABEL, WebviewUrl::App("switcher.html".into()))
+        .title("Chat switcher")
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .visible_on_all_workspaces(always_visible)
+        .build()?;
+
+    Ok(window)
+}
+
+/// Toggles whether the switcher window floats on all virtual
+/// desktops/workspaces at runtime.
+pub fn set_always_visible(app: &AppHandle, always_visible: bool) -> tauri::Result<()> {
+    let window = create_switcher_window(app, always_visible)?;
+    window.set_visible_on_all_workspaces(always_visible)
+}
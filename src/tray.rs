@@ -0,0 +1,117 @@
+// System tray subsystem: aggregate unread badge and quick-switch menu.
+
+use crate::ui::Room;
+use crate::AppState;
+use tauri::{
+    image::Image,
+    menu::{Menu, MenuItem, PredefinedMenuItem},
+    tray::TrayIcon,
+    tray::TrayIconBuilder,
+    AppHandle, Manager,
+};
+
+const SHOW_SWITCHER_ID: &str = "show_switcher";
+const ROOM_ID_PREFIX: &str = "room:";
+
+const ICON_NORMAL: &[u8] = include_bytes!("../icons/tray-normal.png");
+const ICON_UNREAD: &[u8] = include_bytes!("../icons/tray-unread.png");
+
+/// Builds the tray icon and wires up menu activation. Call once from
+/// `main.rs`'s `.setup()`.
+pub fn init_tray(app: &AppHandle) -> tauri::Result<TrayIcon> {
+    let menu = build_menu(app, &[])?;
+
+    let tray = TrayIconBuilder::new()
+        .icon(Image::from_bytes(ICON_NORMAL)?)
+        .menu(&menu)
+        .show_menu_on_left_click(true)
+        .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()))
+        .build(app)?;
+
+    Ok(tray)
+}
+
+/// Rebuilds the tray menu from the current room list so each entry
+/// activates a room, keeping a fixed "Show switcher" item at the bottom.
+///
+/// `rooms` must be the full room snapshot, not just whichever room changed —
+/// this replaces the menu wholesale rather than patching one entry.
+pub fn rebuild_room_menu(app: &AppHandle, tray: &TrayIcon, rooms: &[Room]) -> tauri::Result<()> {
+    let menu = build_menu(app, rooms)?;
+    tray.set_menu(Some(menu))?;
+    Ok(())
+}
+
+fn build_menu(app: &AppHandle, rooms: &[Room]) -> tauri::Result<Menu<tauri::Wry>> {
+    let menu = Menu::new(app)?;
+
+    for room in rooms {
+        let label = if room.unread_count > 0 {
+            format!("{} ({})", room.name, room.unread_count)
+        } else {
+            room.name.clone()
+        };
+        let item = MenuItem::with_id(app, format!("{ROOM_ID_PREFIX}{}", room.id), label, true, None::<&str>)?;
+        menu.append(&item)?;
+    }
+
+    if !rooms.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    let show_switcher = MenuItem::with_id(app, SHOW_SWITCHER_ID, "Show switcher", true, None::<&str>)?;
+    menu.append(&show_switcher)?;
+
+    Ok(menu)
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    if id == SHOW_SWITCHER_ID {
+        if let Some(state) = app.try_state::<AppState>() {
+            tauri::async_runtime::spawn({
+                let ui = state.ui.clone();
+                async move {
+                    ui.lock().await.switcher.show(true);
+                }
+            });
+        }
+        if let Some(window) = app.get_webview_window(crate::switcher::SWITCHER_LABEL) {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        return;
+    }
+
+    if let Some(room_id) = id.strip_prefix(ROOM_ID_PREFIX) {
+        let room_id = room_id.to_string();
+        if let Some(state) = app.try_state::<AppState>() {
+            tauri::async_runtime::spawn({
+                let ui = state.ui.clone();
+                async move {
+                    ui.lock().await.update_current_room(Some(room_id));
+                }
+            });
+        }
+        focus_main_window(app);
+    }
+}
+
+fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Swaps between the "normal" and "has-unread" tray icons based on whether
+/// any room in the carousel currently has unread messages.
+pub fn set_unread(tray: &TrayIcon, has_unread: bool) -> tauri::Result<()> {
+    let icon = if has_unread { ICON_UNREAD } else { ICON_NORMAL };
+    tray.set_icon(Some(Image::from_bytes(icon)?))
+}
+
+/// Sums unread state across the carousel's avatars to decide whether the
+/// tray should show its "has-unread" icon.
+pub fn carousel_has_unread(carousel: &crate::ui::ChatCarousel) -> bool {
+    carousel.avatars.iter().any(|avatar| avatar.has_unread)
+}
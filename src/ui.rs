@@ -49,6 +49,24 @@ impl ChatCarousel {
             .collect();
     }
 
+    /// Updates the avatar for `room` in place if it's already in the list,
+    /// or appends it otherwise, leaving every other avatar untouched. Used
+    /// to apply a single `RoomUpdated` event without wiping the rest of the
+    /// carousel.
+    pub fn upsert_avatar(&mut self, room: &Room) {
+        let avatar = Avatar {
+            room_id: room.id.clone(),
+            url: room.avatar_url.clone(),
+            display_name: room.name.clone(),
+            has_unread: room.unread_count > 0,
+        };
+
+        match self.avatars.iter_mut().find(|existing| existing.room_id == room.id) {
+            Some(existing) => *existing = avatar,
+            None => self.avatars.push(avatar),
+        }
+    }
+
     pub fn filter_by_desktop(&mut self, desktop_id: u32, room_ids: &[String]) {
         if self.filtered_by_desktop {
             self.avatars.retain(|avatar| room_ids.contains(&avatar.room_id));
@@ -71,16 +89,24 @@ pub struct ChatSwitcher {
     pub visible: bool,
     pub search_query: String,
     pub filtered_rooms: Vec<Room>,
+    /// Matched byte indices into whichever field ranked each room in
+    /// `filtered_rooms`, parallel to it, so the UI can highlight them.
+    pub match_highlights: Vec<Vec<usize>>,
     pub selected_index: usize,
     pub global_search: bool,
 }
 
+/// A name match always outranks a match found only in `last_message`,
+/// regardless of how the subsequence itself scores.
+const NAME_FIELD_BONUS: i64 = 1_000_000;
+
 impl ChatSwitcher {
     pub fn new() -> Self {
         Self {
             visible: false,
             search_query: String::new(),
             filtered_rooms: Vec::new(),
+            match_highlights: Vec::new(),
             selected_index: 0,
             global_search: false,
         }
@@ -97,24 +123,37 @@ impl ChatSwitcher {
         self.visible = false;
         self.search_query.clear();
         self.filtered_rooms.clear();
+        self.match_highlights.clear();
         self.selected_index = 0;
     }
 
+    /// Ranks rooms by fuzzy subsequence match against `query`, preferring
+    /// the room name over `last_message` and the best-scoring match overall.
+    /// An empty query returns every room in its original order.
     pub fn update_search(&mut self, query: String, rooms: &[Room]) {
         self.search_query = query;
-        self.filtered_rooms = rooms
+
+        if self.search_query.is_empty() {
+            self.filtered_rooms = rooms.to_vec();
+            self.match_highlights = vec![Vec::new(); rooms.len()];
+            self.selected_index = 0;
+            return;
+        }
+
+        let mut matches: Vec<(i64, Vec<usize>, Room)> = rooms
             .iter()
-            .filter(|room| {
-                room.name.to_lowercase().contains(&self.search_query.to_lowercase())
-                    || room.last_message
-                        .as_ref()
-                        .map(|msg| msg.to_lowercase().contains(&self.search_query.to_lowercase()))
-                        .unwrap_or(false)
-            })
-            .cloned()
+            .filter_map(|room| score_room(&self.search_query, room).map(|(score, indices)| (score, indices, room.clone())))
             .collect();
-        
-        // Reset selection to first item
+
+        matches.sort_by(|(score_a, _, room_a), (score_b, _, room_b)| {
+            score_b
+                .cmp(score_a)
+                .then_with(|| room_a.name.len().cmp(&room_b.name.len()))
+                .then_with(|| room_a.name.cmp(&room_b.name))
+        });
+
+        self.match_highlights = matches.iter().map(|(_, indices, _)| indices.clone()).collect();
+        self.filtered_rooms = matches.into_iter().map(|(_, _, room)| room).collect();
         self.selected_index = 0;
     }
 
@@ -139,6 +178,91 @@ impl ChatSwitcher {
     }
 }
 
+/// Scores a room against `query`, preferring a match in `name` over one
+/// found only in `last_message`.
+fn score_room(query: &str, room: &Room) -> Option<(i64, Vec<usize>)> {
+    if let Some((score, indices)) = fuzzy_match(query, &room.name) {
+        return Some((score + NAME_FIELD_BONUS, indices));
+    }
+
+    room.last_message.as_deref().and_then(|last_message| fuzzy_match(query, last_message))
+}
+
+/// Matches `query` against `target` as an ordered, case-insensitive
+/// subsequence, returning a score and the matched byte indices into
+/// `target`, or `None` if not every query character was found in order.
+///
+/// Scoring rewards consecutive runs (bonus grows with run length), matches
+/// right at the start or after a separator/case boundary, and exact-case
+/// matches, while penalizing the gap before the first match and the gaps
+/// between matched characters.
+fn fuzzy_match(query: &str, target: &str) -> Option<(i64, Vec<usize>)> {
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    // Keyed by char position (for adjacency/gap scoring) but carrying the
+    // byte offset too, so the indices we record can be used to slice the
+    // original `target` string for highlighting.
+    let target_chars: Vec<(usize, char)> = target.char_indices().collect();
+    let query_chars: Vec<char> = query.chars().collect();
+
+    let mut indices = Vec::with_capacity(query_chars.len());
+    let mut score: i64 = 0;
+    let mut query_idx = 0;
+    let mut last_matched: Option<usize> = None;
+    let mut run_length: i64 = 0;
+
+    for (target_idx, &(byte_idx, target_char)) in target_chars.iter().enumerate() {
+        if query_idx >= query_chars.len() {
+            break;
+        }
+
+        let query_char = query_chars[query_idx];
+        if !chars_eq_ignore_case(target_char, query_char) {
+            continue;
+        }
+
+        match last_matched {
+            Some(previous) if target_idx == previous + 1 => {
+                run_length += 1;
+                score += 10 * run_length;
+            }
+            Some(previous) => {
+                run_length = 0;
+                score -= (target_idx - previous - 1) as i64;
+            }
+            None => {
+                score -= target_idx as i64;
+                let at_boundary = target_idx == 0
+                    || matches!(target_chars[target_idx - 1].1, '_' | '-' | ' ' | '.')
+                    || (target_chars[target_idx - 1].1.is_lowercase() && target_char.is_uppercase());
+                if at_boundary {
+                    score += 15;
+                }
+            }
+        }
+
+        if target_char == query_char {
+            score += 5;
+        }
+
+        indices.push(byte_idx);
+        last_matched = Some(target_idx);
+        query_idx += 1;
+    }
+
+    if query_idx < query_chars.len() {
+        return None;
+    }
+
+    Some((score, indices))
+}
+
+fn chars_eq_ignore_case(a: char, b: char) -> bool {
+    a.to_lowercase().eq(b.to_lowercase())
+}
+
 #[derive(Debug)]
 pub struct UIState {
     pub carousel: ChatCarousel,
@@ -164,4 +288,69 @@ impl UIState {
     pub fn set_window_focus(&mut self, focused: bool) {
         self.window_focused = focused;
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(id: &str, name: &str, last_message: Option<&str>) -> Room {
+        Room {
+            id: id.to_string(),
+            name: name.to_string(),
+            avatar_url: None,
+            last_message: last_message.map(str::to_string),
+            unread_count: 0,
+        }
+    }
+
+    #[test]
+    fn empty_query_returns_all_rooms_in_original_order() {
+        let rooms = vec![room("1", "Zebra", None), room("2", "Apple", None)];
+        let mut switcher = ChatSwitcher::new();
+        switcher.update_search(String::new(), &rooms);
+
+        assert_eq!(switcher.filtered_rooms.iter().map(|r| r.id.clone()).collect::<Vec<_>>(), vec!["1", "2"]);
+        assert_eq!(switcher.selected_index, 0);
+    }
+
+    #[test]
+    fn rejects_non_subsequence_matches() {
+        assert!(fuzzy_match("xyz", "matrix").is_none());
+    }
+
+    #[test]
+    fn name_match_outranks_last_message_only_match() {
+        let rooms = vec![
+            room("1", "Random chat", Some("team")),
+            room("2", "Team updates", None),
+        ];
+        let mut switcher = ChatSwitcher::new();
+        switcher.update_search("team".to_string(), &rooms);
+
+        assert_eq!(switcher.filtered_rooms[0].id, "2");
+    }
+
+    #[test]
+    fn consecutive_runs_score_higher_than_scattered_matches() {
+        let (scattered, _) = fuzzy_match("ab", "a-b").unwrap();
+        let (consecutive, _) = fuzzy_match("ab", "ab-").unwrap();
+        assert!(consecutive > scattered);
+    }
+
+    #[test]
+    fn match_indices_are_byte_offsets_not_char_positions() {
+        let (_, indices) = fuzzy_match("cd", "\u{1F600}bcd").unwrap();
+        assert_eq!(indices, vec![5, 6]);
+        assert_eq!(&"\u{1F600}bcd"[indices[0]..indices[0] + 1], "c");
+    }
+
+    #[test]
+    fn selected_index_resets_to_first_match() {
+        let rooms = vec![room("1", "Alpha", None), room("2", "Beta", None)];
+        let mut switcher = ChatSwitcher::new();
+        switcher.select_next();
+        switcher.update_search("a".to_string(), &rooms);
+        assert_eq!(switcher.selected_index, 0);
+    }
 }
\ No newline at end of file